@@ -0,0 +1,182 @@
+#![allow(non_camel_case_types, dead_code)]
+
+//! Hand-written mirror of the subset of `<linux/connector.h>` and
+//! `<linux/cn_proc.h>` this crate talks to. A real bindgen run against the
+//! running kernel's uapi headers would produce something equivalent to this;
+//! it's checked in directly so the crate builds without a bindgen build
+//! dependency or network access to kernel headers.
+
+pub const NETLINK_CONNECTOR: u32 = 11;
+pub const NETLINK_NO_ENOBUFS: u32 = 5;
+
+pub const NLMSG_NOOP: u32 = 0x1;
+pub const NLMSG_ERROR: u32 = 0x2;
+pub const NLMSG_DONE: u32 = 0x3;
+
+pub const CN_IDX_PROC: u32 = 0x1;
+pub const CN_VAL_PROC: u32 = 0x1;
+
+pub type proc_cn_mcast_op = u32;
+pub const PROC_CN_MCAST_LISTEN: proc_cn_mcast_op = 1;
+pub const PROC_CN_MCAST_IGNORE: proc_cn_mcast_op = 2;
+
+pub const PROC_EVENT_NONE: u32 = 0x0000_0000;
+pub const PROC_EVENT_FORK: u32 = 0x0000_0001;
+pub const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+pub const PROC_EVENT_UID: u32 = 0x0000_0004;
+pub const PROC_EVENT_GID: u32 = 0x0000_0040;
+pub const PROC_EVENT_SID: u32 = 0x0000_0080;
+pub const PROC_EVENT_PTRACE: u32 = 0x0000_0100;
+pub const PROC_EVENT_COMM: u32 = 0x0000_0200;
+pub const PROC_EVENT_NONZERO_EXIT: u32 = 0x2000_0000;
+pub const PROC_EVENT_COREDUMP: u32 = 0x4000_0000;
+pub const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct sockaddr_nl {
+    pub nl_family: u16,
+    nl_pad: u16,
+    pub nl_pid: u32,
+    pub nl_groups: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct nlmsghdr {
+    pub nlmsg_len: u32,
+    pub nlmsg_type: u16,
+    pub nlmsg_flags: u16,
+    pub nlmsg_seq: u32,
+    pub nlmsg_pid: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct cb_id {
+    pub idx: u32,
+    pub val: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct cn_msg {
+    pub id: cb_id,
+    pub seq: u32,
+    pub ack: u32,
+    pub len: u16,
+    pub flags: u16,
+    pub data: [u8; 0],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct proc_input {
+    pub mcast_op: proc_cn_mcast_op,
+    pub event_type: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct fork_proc_event {
+    pub parent_pid: i32,
+    pub parent_tgid: i32,
+    pub child_pid: i32,
+    pub child_tgid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct exec_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union id_proc_event_r {
+    pub ruid: u32,
+    pub rgid: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union id_proc_event_e {
+    pub euid: u32,
+    pub egid: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct id_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+    pub r: id_proc_event_r,
+    pub e: id_proc_event_e,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sid_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ptrace_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+    pub tracer_pid: i32,
+    pub tracer_tgid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct comm_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+    pub comm: [libc::c_char; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct coredump_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+    pub parent_pid: i32,
+    pub parent_tgid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct exit_proc_event {
+    pub process_pid: i32,
+    pub process_tgid: i32,
+    pub exit_code: u32,
+    pub exit_signal: u32,
+    pub parent_pid: i32,
+    pub parent_tgid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union proc_event_data {
+    pub fork: fork_proc_event,
+    pub exec: exec_proc_event,
+    pub id: id_proc_event,
+    pub sid: sid_proc_event,
+    pub ptrace: ptrace_proc_event,
+    pub comm: comm_proc_event,
+    pub coredump: coredump_proc_event,
+    pub exit: exit_proc_event,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct proc_event {
+    pub what: u32,
+    pub cpu: u32,
+    pub timestamp_ns: u64,
+    pub event_data: proc_event_data,
+}